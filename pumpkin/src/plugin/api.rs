@@ -0,0 +1,113 @@
+use std::{path::PathBuf, sync::Arc};
+
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use crate::permission::PermissionManager;
+use crate::server::Server;
+
+use super::{HandlerMap, PluginManager};
+
+/// Metadata describing a plugin, typically provided by the `#[plugin_method]`/`pumpkin_plugin!`
+/// macros at the plugin's entry point.
+#[derive(Debug, Clone)]
+pub struct PluginMetadata<'a> {
+    pub name: &'a str,
+    pub version: &'a str,
+    pub authors: &'a str,
+    pub description: &'a str,
+    /// Plugins that must already be loaded before this one, by name. Missing a hard
+    /// dependency fails the load with `ManagerError::DependencyMissing`.
+    pub depends: &'a [&'a str],
+    /// Plugins that should be loaded first if present, but whose absence is not an error.
+    pub soft_depends: &'a [&'a str],
+}
+
+/// The entry point every plugin must implement.
+#[async_trait]
+pub trait Plugin: Send + Sync {
+    /// Called once the plugin has been loaded and its dependencies are satisfied.
+    async fn on_load(&mut self, server: &Context) -> Result<(), String>;
+
+    /// Called before the plugin is unloaded.
+    async fn on_unload(&mut self, server: &Context) -> Result<(), String>;
+}
+
+/// Handed to a plugin on load/unload so it can register event handlers, look up permissions,
+/// and reach the running server.
+#[derive(Clone)]
+pub struct Context {
+    pub metadata: PluginMetadata<'static>,
+    pub server: Arc<Server>,
+    handlers: Arc<RwLock<HandlerMap>>,
+    plugin_manager: Arc<RwLock<PluginManager>>,
+    permission_manager: Arc<RwLock<PermissionManager>>,
+}
+
+impl Context {
+    #[must_use]
+    pub fn new(
+        metadata: PluginMetadata<'static>,
+        server: Arc<Server>,
+        handlers: Arc<RwLock<HandlerMap>>,
+        plugin_manager: Arc<RwLock<PluginManager>>,
+        permission_manager: Arc<RwLock<PermissionManager>>,
+    ) -> Self {
+        Self {
+            metadata,
+            server,
+            handlers,
+            plugin_manager,
+            permission_manager,
+        }
+    }
+}
+
+/// Priority tier a handler is registered under; lower values run first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EventPriority {
+    Highest,
+    High,
+    Normal,
+    Low,
+    Lowest,
+    /// Always runs last and should not mutate the event, only observe the final outcome.
+    Monitor,
+}
+
+/// A dynamically dispatched server event.
+pub trait Event: Send + Sync {
+    /// The event's name, looked up on a concrete instance.
+    fn get_name(&self) -> &'static str;
+
+    /// The event's name, looked up at the type level so handlers can be matched before
+    /// an instance exists.
+    fn get_name_static() -> &'static str
+    where
+        Self: Sized;
+
+    fn as_any(&self) -> &dyn std::any::Any;
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
+
+    /// Expose this event as [`Cancellable`] if it supports being vetoed by a handler. Events
+    /// that can't be cancelled (most of them) just keep the default `None`.
+    fn as_cancellable(&self) -> Option<&dyn Cancellable> {
+        None
+    }
+
+    /// Mutable counterpart of [`Event::as_cancellable`], through which a handler can cancel
+    /// the event. `PluginManager::fire` reads the cancellation state via `as_cancellable`.
+    fn as_cancellable_mut(&mut self) -> Option<&mut dyn Cancellable> {
+        None
+    }
+}
+
+/// Implemented by events that a blocking handler can veto (e.g. cancel a block-place or a
+/// chat message) before the server acts on them.
+pub trait Cancellable {
+    /// Whether a handler has already cancelled this event.
+    fn is_cancelled(&self) -> bool;
+
+    /// Cancel (or un-cancel) the event.
+    fn set_cancelled(&mut self, cancelled: bool);
+}
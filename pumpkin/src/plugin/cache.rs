@@ -0,0 +1,324 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::PluginMetadata;
+
+/// Bumped whenever the on-disk record layout changes, so a future version can tell an old
+/// cache apart from a corrupt one instead of guessing.
+const CACHE_FORMAT_VERSION: u8 = 1;
+
+/// Owned, serializable mirror of [`PluginMetadata`], which borrows `&'static str`s that only
+/// exist once a plugin's library is actually loaded. Round-tripping through the cache leaks
+/// the strings back to `'static` on read; this is a deliberate, bounded (one leak per loaded
+/// plugin, once per boot) trade-off to keep `PluginMetadata` itself borrow-based everywhere
+/// else.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedMetadata {
+    name: String,
+    version: String,
+    authors: String,
+    description: String,
+    depends: Vec<String>,
+    soft_depends: Vec<String>,
+}
+
+impl CachedMetadata {
+    fn from_metadata(metadata: &PluginMetadata<'static>) -> Self {
+        Self {
+            name: metadata.name.to_string(),
+            version: metadata.version.to_string(),
+            authors: metadata.authors.to_string(),
+            description: metadata.description.to_string(),
+            depends: metadata.depends.iter().map(ToString::to_string).collect(),
+            soft_depends: metadata.soft_depends.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn into_metadata(self) -> PluginMetadata<'static> {
+        let leak_list = |list: Vec<String>| -> &'static [&'static str] {
+            let leaked: Vec<&'static str> = list
+                .into_iter()
+                .map(|s| -> &'static str { Box::leak(s.into_boxed_str()) })
+                .collect();
+            Box::leak(leaked.into_boxed_slice())
+        };
+
+        PluginMetadata {
+            name: Box::leak(self.name.into_boxed_str()),
+            version: Box::leak(self.version.into_boxed_str()),
+            authors: Box::leak(self.authors.into_boxed_str()),
+            description: Box::leak(self.description.into_boxed_str()),
+            depends: leak_list(self.depends),
+            soft_depends: leak_list(self.soft_depends),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    mtime: u64,
+    size: u64,
+    metadata: CachedMetadata,
+}
+
+/// Per-boot cache of plugin metadata keyed by file path, so `load_plugins` doesn't have to
+/// dynamically open every plugin on every restart just to read its metadata.
+///
+/// Stored at `<plugin_dir>/.plugin-cache.msgpackz`: a version byte, followed by one
+/// length-prefixed, Brotli-compressed, `rmp-serde`-encoded `(path, CacheEntry)` pair per
+/// plugin. Framing
+/// per-entry (rather than one big blob) means a single corrupt record only invalidates that
+/// plugin's cache hit instead of the whole file.
+#[derive(Default)]
+pub struct PluginCache {
+    entries: HashMap<PathBuf, CacheEntry>,
+    /// Set when the on-disk file was missing, unreadable, on a stale format version, or
+    /// truncated mid-frame. [`Self::upsert`] rewrites the whole file exactly once to repair it
+    /// when this is set, then clears it so later upserts in the same boot append a single
+    /// frame instead of rewriting everything that's already known to be on disk and intact.
+    needs_rewrite: bool,
+}
+
+impl PluginCache {
+    /// Load the cache file, tolerating a missing or fully unreadable file by starting empty.
+    pub fn load(cache_path: &Path) -> Self {
+        let fresh = || Self {
+            entries: HashMap::new(),
+            needs_rewrite: true,
+        };
+
+        let Ok(mut file) = std::fs::File::open(cache_path) else {
+            return fresh();
+        };
+
+        let mut bytes = Vec::new();
+        if file.read_to_end(&mut bytes).is_err() || bytes.is_empty() {
+            return fresh();
+        }
+
+        let (&version, mut rest) = match bytes.split_first() {
+            Some(split) => split,
+            None => return fresh(),
+        };
+        if version != CACHE_FORMAT_VERSION {
+            log::warn!(
+                "Plugin metadata cache is format version {version}, expected {CACHE_FORMAT_VERSION}; ignoring it"
+            );
+            return fresh();
+        }
+
+        let mut entries = HashMap::new();
+        let mut needs_rewrite = false;
+        while rest.len() >= 4 {
+            let (len_bytes, after_len) = rest.split_at(4);
+            let len = u32::from_le_bytes(len_bytes.try_into().unwrap()) as usize;
+            if after_len.len() < len {
+                log::warn!("Plugin metadata cache is truncated; stopping read early");
+                // A truncated trailing frame can't be framed around safely - appending new
+                // frames after it would leave a length prefix whose declared size now spans
+                // into the new data. Rewrite from the entries we did manage to read instead.
+                needs_rewrite = true;
+                break;
+            }
+            let (frame, after_frame) = after_len.split_at(len);
+            rest = after_frame;
+
+            match Self::decode_entry(frame) {
+                Ok((path, entry)) => {
+                    entries.insert(path, entry);
+                }
+                Err(e) => log::warn!("Skipping corrupt plugin cache entry: {e}"),
+            }
+        }
+
+        Self { entries, needs_rewrite }
+    }
+
+    fn decode_entry(frame: &[u8]) -> Result<(PathBuf, CacheEntry), String> {
+        let mut decompressed = Vec::new();
+        brotli::BrotliDecompress(&mut std::io::Cursor::new(frame), &mut decompressed)
+            .map_err(|e| format!("brotli decode failed: {e}"))?;
+
+        let (path, entry): (PathBuf, CacheEntry) =
+            rmp_serde::from_slice(&decompressed).map_err(|e| format!("msgpack decode failed: {e}"))?;
+
+        Ok((path, entry))
+    }
+
+    /// Look up a cached metadata hit, but only if the file's mtime and size still match what
+    /// was recorded; anything else (including a never-seen path) is a miss.
+    pub fn lookup(&self, path: &Path, mtime: u64, size: u64) -> Option<PluginMetadata<'static>> {
+        let entry = self.entries.get(path)?;
+        if entry.mtime != mtime || entry.size != size {
+            return None;
+        }
+        Some(entry.metadata.clone().into_metadata())
+    }
+
+    /// Record or refresh a single plugin's metadata and immediately persist just that change,
+    /// rather than buffering updates for one wholesale rewrite at the end of the scan.
+    ///
+    /// Normally this appends a single length-prefixed frame for `path` to the existing file; a
+    /// later frame for a path already in the file simply shadows the earlier one, since
+    /// [`Self::load`] keeps inserting into the same map as it reads frames in order. The file
+    /// is only rewritten wholesale when [`Self::load`] found it missing, stale, or truncated,
+    /// and then only once per boot.
+    pub fn upsert(&mut self, cache_path: &Path, path: PathBuf, mtime: u64, size: u64, metadata: &PluginMetadata<'static>) {
+        let entry = CacheEntry {
+            mtime,
+            size,
+            metadata: CachedMetadata::from_metadata(metadata),
+        };
+        self.entries.insert(path.clone(), entry.clone());
+
+        let result = if self.needs_rewrite {
+            self.rewrite(cache_path)
+        } else {
+            Self::append_entry(cache_path, &path, &entry)
+        };
+
+        match result {
+            Ok(()) => self.needs_rewrite = false,
+            Err(e) => log::warn!("Failed to persist plugin metadata cache: {e}"),
+        }
+    }
+
+    /// File mtime (seconds since the epoch) and size, used as the cache's change-detection key.
+    pub fn stat(path: &Path) -> std::io::Result<(u64, u64)> {
+        let metadata = std::fs::metadata(path)?;
+        let mtime = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Ok((mtime, metadata.len()))
+    }
+
+    /// Encode a single `(path, entry)` pair into its on-disk frame: a 4-byte little-endian
+    /// length prefix followed by the Brotli-compressed, `rmp-serde`-encoded payload.
+    fn encode_frame(path: &Path, entry: &CacheEntry) -> std::io::Result<Vec<u8>> {
+        let payload = rmp_serde::to_vec(&(path, entry))
+            .map_err(|e| std::io::Error::other(format!("msgpack encode failed: {e}")))?;
+
+        let mut compressed = Vec::new();
+        let params = brotli::enc::BrotliEncoderParams::default();
+        brotli::BrotliCompress(&mut std::io::Cursor::new(payload), &mut compressed, &params)?;
+
+        let mut frame = (compressed.len() as u32).to_le_bytes().to_vec();
+        frame.extend_from_slice(&compressed);
+        Ok(frame)
+    }
+
+    /// Append a single plugin's frame to the end of the cache file, creating it (with the
+    /// version byte) if it doesn't exist yet. This is the common case: an unchanged boot only
+    /// ever grows the file by the handful of plugins that are new or modified, rather than
+    /// re-encoding every already-cached entry.
+    fn append_entry(cache_path: &Path, path: &Path, entry: &CacheEntry) -> std::io::Result<()> {
+        if let Some(parent) = cache_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let write_header = !cache_path.exists();
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(cache_path)?;
+
+        if write_header {
+            file.write_all(&[CACHE_FORMAT_VERSION])?;
+        }
+        file.write_all(&Self::encode_frame(path, entry)?)
+    }
+
+    /// Rewrite the cache file from scratch using every currently known in-memory entry.
+    ///
+    /// Used only to repair a file [`Self::load`] found missing, on a stale format version, or
+    /// truncated mid-frame - once that's done, further changes within the same boot are
+    /// appended one frame at a time via [`Self::append_entry`] instead.
+    fn rewrite(&self, cache_path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = cache_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut out = vec![CACHE_FORMAT_VERSION];
+        for (path, entry) in &self.entries {
+            out.extend_from_slice(&Self::encode_frame(path, entry)?);
+        }
+
+        let tmp_path = cache_path.with_extension("msgpackz.tmp");
+        {
+            let mut tmp = std::fs::File::create(&tmp_path)?;
+            tmp.write_all(&out)?;
+        }
+        std::fs::rename(tmp_path, cache_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_entry() -> CacheEntry {
+        let metadata = PluginMetadata {
+            name: "example",
+            version: "1.0.0",
+            authors: "Someone",
+            description: "An example plugin",
+            depends: &["other"],
+            soft_depends: &[],
+        };
+        CacheEntry {
+            mtime: 1234,
+            size: 42,
+            metadata: CachedMetadata::from_metadata(&metadata),
+        }
+    }
+
+    #[test]
+    fn frame_round_trips_through_encode_and_decode() {
+        let path = PathBuf::from("/plugins/example.so");
+        let entry = sample_entry();
+
+        let frame = PluginCache::encode_frame(&path, &entry).unwrap();
+        // encode_frame's 4-byte length prefix isn't part of what decode_entry reads.
+        let (decoded_path, decoded_entry) = PluginCache::decode_entry(&frame[4..]).unwrap();
+
+        assert_eq!(decoded_path, path);
+        assert_eq!(decoded_entry.mtime, entry.mtime);
+        assert_eq!(decoded_entry.size, entry.size);
+        assert_eq!(decoded_entry.metadata.name, entry.metadata.name);
+    }
+
+    #[test]
+    fn a_later_frame_for_the_same_path_shadows_an_earlier_one_on_load() {
+        let dir = std::env::temp_dir().join(format!(
+            "pumpkin-plugin-cache-test-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cache_path = dir.join("cache.msgpackz");
+        let _ = std::fs::remove_file(&cache_path);
+
+        let path = PathBuf::from("/plugins/example.so");
+        let mut first = sample_entry();
+        first.mtime = 1;
+        PluginCache::append_entry(&cache_path, &path, &first).unwrap();
+
+        let mut second = sample_entry();
+        second.mtime = 2;
+        PluginCache::append_entry(&cache_path, &path, &second).unwrap();
+
+        let loaded = PluginCache::load(&cache_path);
+        assert!(!loaded.needs_rewrite);
+        assert_eq!(loaded.lookup(&path, 2, second.size).unwrap().name, "example");
+        assert!(loaded.lookup(&path, 1, first.size).is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,31 @@
+/// Server-level configuration for the plugin manager itself (as opposed to
+/// [`crate::plugin::configuration`], which each plugin uses for its own settings).
+#[derive(Debug, Clone)]
+pub struct PluginManagerConfig {
+    /// Directory the manager scans for plugin artifacts.
+    pub plugin_dir: String,
+    /// File stems (filename without extension) to filter out of the scan.
+    pub blacklist: Vec<String>,
+    /// When `true`, `blacklist` is treated as the only set of stems allowed to load instead
+    /// of a set to exclude.
+    pub as_whitelist: bool,
+}
+
+impl PluginManagerConfig {
+    /// Whether a plugin file with the given stem should be skipped during `load_plugins`.
+    #[must_use]
+    pub fn is_filtered(&self, stem: &str) -> bool {
+        let listed = self.blacklist.iter().any(|entry| entry == stem);
+        if self.as_whitelist { !listed } else { listed }
+    }
+}
+
+impl Default for PluginManagerConfig {
+    fn default() -> Self {
+        Self {
+            plugin_dir: "./plugins".to_string(),
+            blacklist: Vec::new(),
+            as_whitelist: false,
+        }
+    }
+}
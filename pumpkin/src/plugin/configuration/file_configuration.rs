@@ -1,10 +1,19 @@
 use crate::plugin::configuration::Configuration;
+use crate::plugin::configuration::format::FileFormat;
+use crate::plugin::configuration::merge;
+use crate::plugin::configuration::watch::ConfigWatcher;
 use anyhow::Result;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
+/// How many files deep an `import:` chain may go, counting the requested file itself (so at
+/// most `IMPORT_RECURSION_LIMIT - 1` hops are followed). Guards against a
+/// config accidentally (or maliciously) re-importing an ancestor and recursing forever.
+const IMPORT_RECURSION_LIMIT: usize = 5;
+
 /// Manages configuration files for plugins
+#[derive(Clone)]
 pub struct FileConfiguration {
     plugin_name: String,
     data_folder: PathBuf,
@@ -21,50 +30,258 @@ impl FileConfiguration {
         }
     }
 
-    /// Load a configuration file, creating it from embedded resources if it doesn't exist
+    /// Load a configuration file, layering it over the embedded default and environment
+    /// variables.
+    ///
+    /// The format (YAML, TOML, or JSON) is auto-detected from `filename`'s extension, so a
+    /// plugin can ship e.g. `config.toml` and have it parsed correctly without any extra
+    /// setup; the embedded default, if present, is parsed with that same detected format.
+    ///
+    /// Three layers are resolved, lowest priority first: the embedded default (always used as
+    /// a base, not just when the file is missing), the on-disk file (deep-merged over it, so
+    /// overriding one field of a nested section doesn't wipe out the default's other fields),
+    /// and finally `PUMPKIN_<PLUGIN>_<KEY>` environment variables, which the returned
+    /// [`Configuration`]'s accessors check ahead of both of the above.
+    ///
+    /// If the parsed file has a top-level `import` key (a path, or a list of paths, resolved
+    /// against `data_folder`), those files are loaded first and deep-merged underneath it, so
+    /// a large config can be split across several files. Keys in `filename` itself always win
+    /// over anything it imports; among multiple imports, later entries win over earlier ones.
+    ///
+    /// If the embedded default's `config-version` is higher than the on-disk file's, the new
+    /// keys it introduces are written back into the file (see [`Self::migrate_if_needed`]).
     pub async fn load_config(&self, filename: &str) -> Result<Configuration, String> {
-        let config_path = self.data_folder.join(filename);
+        let mut chain = Vec::new();
+        let data = self.load_raw(filename, true, &mut chain).await?;
 
-        // Ensure the data folder exists
-        if let Some(parent) = config_path.parent() {
-            if !parent.exists() {
-                fs::create_dir_all(parent)
-                    .await
-                    .map_err(|e| format!("Failed to create data folder: {}", e))?;
-            }
+        if let Err(e) = self.migrate_if_needed(filename).await {
+            log::warn!("[{}] Config migration check failed: {}", self.plugin_name, e);
         }
 
-        // Load the configuration from file if it exists, otherwise use embedded default
-        let config_content = if config_path.exists() {
-            fs::read_to_string(&config_path)
+        Ok(Configuration {
+            data,
+            env_prefix: self.plugin_name.to_uppercase(),
+        })
+    }
+
+    /// Recursive worker behind [`Self::load_config`]. `allow_embedded_fallback` is `true` only
+    /// for the top-level file being requested; an imported file has no embedded resource of
+    /// its own to fall back to, so it errors if it's missing from disk.
+    fn load_raw<'a>(
+        &'a self,
+        filename: &'a str,
+        allow_embedded_fallback: bool,
+        chain: &'a mut Vec<PathBuf>,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<HashMap<String, serde_yaml::Value>, String>> + Send + 'a>>
+    {
+        Box::pin(async move {
+            if chain.len() >= IMPORT_RECURSION_LIMIT {
+                return Err(format!(
+                    "Config import depth exceeded {IMPORT_RECURSION_LIMIT} while loading {filename}"
+                ));
+            }
+
+            let config_path = self.data_folder.join(filename);
+            // Canonicalize before comparing against the chain, so two different spellings of
+            // the same file (`a.yaml` vs `./a.yaml`, or a path reached through a symlink)
+            // aren't treated as distinct files and allowed to recurse into each other forever.
+            // A path that doesn't exist yet can't already be part of a real chain, so fall
+            // back to the uncanonicalized path for it.
+            let canonical_path = fs::canonicalize(&config_path)
                 .await
-                .map_err(|e| format!("Failed to read config file: {}", e))?
-        } else {
-            // Try to load from embedded resource
-            if let Some(embedded_content) = &self.embedded_resource {
-                String::from_utf8(embedded_content.clone())
-                    .map_err(|e| format!("Invalid UTF-8 in embedded config: {}", e))?
-            } else {
-                return Err(format!("No embedded resource found for {}", filename));
+                .unwrap_or_else(|_| config_path.clone());
+            if chain.contains(&canonical_path) {
+                return Err(format!(
+                    "Config import cycle detected: {} is imported again from within its own import chain",
+                    config_path.display()
+                ));
             }
+
+            // Ensure the data folder exists
+            if let Some(parent) = config_path.parent() {
+                if !parent.exists() {
+                    fs::create_dir_all(parent)
+                        .await
+                        .map_err(|e| format!("Failed to create data folder: {}", e))?;
+                }
+            }
+
+            self.reject_ambiguous_config(filename)?;
+
+            // The embedded default, when present, is always the base layer - not merely a
+            // fallback for when the file is absent.
+            let mut merged = HashMap::new();
+            let mut have_base = false;
+            if allow_embedded_fallback {
+                if let Some(embedded_content) = &self.embedded_resource {
+                    let embedded_str = String::from_utf8(embedded_content.clone())
+                        .map_err(|e| format!("Invalid UTF-8 in embedded config: {}", e))?;
+                    merged = FileFormat::from_filename(filename).parse(&embedded_str)?;
+                    have_base = true;
+                }
+            }
+
+            if !config_path.exists() {
+                if have_base {
+                    return Ok(merged);
+                }
+                return Err(if allow_embedded_fallback {
+                    format!("No embedded resource found for {}", filename)
+                } else {
+                    format!("Imported config file not found: {}", config_path.display())
+                });
+            }
+
+            let config_content = fs::read_to_string(&config_path)
+                .await
+                .map_err(|e| format!("Failed to read config file: {}", e))?;
+            let mut data = FileFormat::from_filename(filename).parse(&config_content)?;
+            let imports = take_import_paths(&mut data);
+
+            if !imports.is_empty() {
+                chain.push(canonical_path);
+                for import in imports {
+                    let imported = self.load_raw(&import, false, chain).await?;
+                    merge::deep_merge(&mut merged, imported);
+                }
+                chain.pop();
+            }
+
+            merge::deep_merge(&mut merged, data);
+            Ok(merged)
+        })
+    }
+
+    /// Errors out if `data_folder` holds more than one file matching `filename`'s stem across
+    /// the known config extensions (e.g. both `config.yaml` and `config.yml`), naming every
+    /// candidate found. Silently picking whichever one happens to be requested leads to
+    /// confusing "my edits do nothing" reports when an operator edits the wrong one.
+    fn reject_ambiguous_config(&self, filename: &str) -> Result<(), String> {
+        let path = Path::new(filename);
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(filename);
+        let dir = match path.parent() {
+            Some(parent) if parent.as_os_str().is_empty() => self.data_folder.clone(),
+            Some(parent) => self.data_folder.join(parent),
+            None => self.data_folder.clone(),
         };
 
-        // Parse the YAML content
-        let config: serde_yaml::Value = serde_yaml::from_str(&config_content)
-            .map_err(|e| format!("Failed to parse YAML: {}", e))?;
-
-        // Convert to our Configuration format
-        let data = if let serde_yaml::Value::Mapping(map) = config {
-            map.into_iter()
-                .filter_map(|(key, value)| {
-                    key.as_str().map(|k| (k.to_string(), value))
-                })
-                .collect()
-        } else {
-            HashMap::new()
+        let candidates: Vec<PathBuf> = FileFormat::all()
+            .iter()
+            .flat_map(|format| format.extensions())
+            .map(|ext| dir.join(format!("{stem}.{ext}")))
+            .filter(|path| path.exists())
+            .collect();
+
+        if candidates.len() > 1 {
+            let listed = candidates
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(format!(
+                "Ambiguous config for `{stem}`: found multiple candidate files ({listed}); consolidate them into one"
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Serialize `config`'s live data back to `filename`, in the format implied by its
+    /// extension, overwriting whatever is there. The write is atomic (temp file in the same
+    /// directory, then renamed into place) so a crash or power loss mid-write can't leave a
+    /// half-written, corrupt config behind.
+    pub async fn save_config(&self, filename: &str, config: &Configuration) -> Result<(), String> {
+        let config_path = self.data_folder.join(filename);
+
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create data folder: {}", e))?;
+        }
+
+        let serialized = FileFormat::from_filename(filename).serialize(&config.data)?;
+
+        let tmp_file_name = format!(
+            "{}.tmp",
+            config_path.file_name().and_then(|n| n.to_str()).unwrap_or(filename)
+        );
+        let tmp_path = config_path.with_file_name(tmp_file_name);
+
+        fs::write(&tmp_path, serialized.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write temp config file: {}", e))?;
+        fs::rename(&tmp_path, &config_path)
+            .await
+            .map_err(|e| format!("Failed to finalize config file: {}", e))?;
+
+        log::info!("[{}] Saved config file: {}", self.plugin_name, filename);
+        Ok(())
+    }
+
+    /// If the embedded default's `config-version` is higher than the on-disk file's (missing
+    /// counts as `0`), write the embedded default deep-merged with the file's own top-level
+    /// content back over the file, logging each key the user's file was missing. Only
+    /// `filename`'s own keys are re-serialized - never `merged` (chunk1-2's post-import,
+    /// fully-resolved tree) - so a config split across files with `import:` keeps its `import`
+    /// key and its imported files untouched instead of being collapsed into one file. A no-op
+    /// when there's no embedded default, no file on disk yet, or the shipped version isn't
+    /// newer.
+    async fn migrate_if_needed(&self, filename: &str) -> Result<(), String> {
+        let Some(embedded_content) = &self.embedded_resource else {
+            return Ok(());
         };
+        let config_path = self.data_folder.join(filename);
+        if !config_path.exists() {
+            return Ok(());
+        }
+
+        let embedded_str = String::from_utf8(embedded_content.clone())
+            .map_err(|e| format!("Invalid UTF-8 in embedded config: {}", e))?;
+        let embedded_data = FileFormat::from_filename(filename).parse(&embedded_str)?;
+
+        let user_content = fs::read_to_string(&config_path)
+            .await
+            .map_err(|e| format!("Failed to read config file: {}", e))?;
+        let user_data = FileFormat::from_filename(filename).parse(&user_content)?;
+
+        if config_version(&embedded_data) <= config_version(&user_data) {
+            return Ok(());
+        }
+
+        let mut added_keys = Vec::new();
+        collect_added_keys(&embedded_data, &user_data, String::new(), &mut added_keys);
+        if added_keys.is_empty() {
+            return Ok(());
+        }
+
+        for key in &added_keys {
+            log::info!(
+                "[{}] Config migration: added new default key `{}` to {}",
+                self.plugin_name, key, filename
+            );
+        }
 
-        Ok(Configuration { data })
+        let mut migrated_data = embedded_data;
+        merge::deep_merge(&mut migrated_data, user_data);
+
+        let migrated = Configuration {
+            data: migrated_data,
+            env_prefix: String::new(),
+        };
+        self.save_config(filename, &migrated).await
+    }
+
+    /// Start watching `filename` for on-disk changes, handing updated configs out through the
+    /// returned [`ConfigWatcher`] instead of requiring the plugin to call [`Self::load_config`]
+    /// again itself. `initial` should be the `Configuration` already obtained from
+    /// [`Self::load_config`], so the watcher has something to serve before the first reload.
+    ///
+    /// Every reload re-runs the real [`Self::load_config`] pipeline (embedded default, layered
+    /// `import:`s, migration check) rather than a bare file parse, so a config that leans on
+    /// any of those still behaves the same way live as it does on a fresh plugin load.
+    pub fn watch_config(&self, filename: &str, initial: Configuration) -> Result<ConfigWatcher, String> {
+        let path = self.data_folder.join(filename);
+        ConfigWatcher::spawn(path, initial, self.plugin_name.clone(), self.clone(), filename.to_string())
     }
 
     /// Save the default configuration file if it doesn't exist
@@ -121,3 +338,124 @@ impl FileConfiguration {
         Ok(())
     }
 }
+
+/// Pulls the reserved `import` key out of a parsed config map, normalizing it to a list of
+/// paths regardless of whether it was written as a single string or a sequence.
+fn take_import_paths(data: &mut HashMap<String, serde_yaml::Value>) -> Vec<String> {
+    match data.remove("import") {
+        Some(serde_yaml::Value::String(path)) => vec![path],
+        Some(serde_yaml::Value::Sequence(paths)) => paths
+            .into_iter()
+            .filter_map(|value| value.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Reads the reserved `config-version` key, defaulting to `0` for files that predate it.
+fn config_version(data: &HashMap<String, serde_yaml::Value>) -> i64 {
+    data.get("config-version").and_then(serde_yaml::Value::as_i64).unwrap_or(0)
+}
+
+/// Recursively collects dotted paths (e.g. `"database.host"`) present in `embedded` but
+/// missing from `user`, so migration can log exactly which new default keys got added.
+fn collect_added_keys(
+    embedded: &HashMap<String, serde_yaml::Value>,
+    user: &HashMap<String, serde_yaml::Value>,
+    prefix: String,
+    added: &mut Vec<String>,
+) {
+    for (key, embedded_value) in embedded {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+
+        match (user.get(key), embedded_value) {
+            (None, _) => added.push(path),
+            (Some(serde_yaml::Value::Mapping(user_map)), serde_yaml::Value::Mapping(embedded_map)) => {
+                let embedded_sub: HashMap<String, serde_yaml::Value> = embedded_map
+                    .iter()
+                    .filter_map(|(k, v)| k.as_str().map(|k| (k.to_string(), v.clone())))
+                    .collect();
+                let user_sub: HashMap<String, serde_yaml::Value> = user_map
+                    .iter()
+                    .filter_map(|(k, v)| k.as_str().map(|k| (k.to_string(), v.clone())))
+                    .collect();
+                collect_added_keys(&embedded_sub, &user_sub, path, added);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(test_name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "pumpkin-file-configuration-test-{}-{test_name}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn import_cycle_is_rejected_instead_of_recursing_forever() {
+        let dir = temp_dir("import-cycle");
+        std::fs::write(dir.join("a.yaml"), "import: b.yaml\nkey: a\n").unwrap();
+        std::fs::write(dir.join("b.yaml"), "import: a.yaml\nkey: b\n").unwrap();
+
+        let config = FileConfiguration::new("test".to_string(), dir.clone(), None);
+        let err = config.load_config("a.yaml").await.unwrap_err();
+        assert!(err.contains("cycle"), "unexpected error: {err}");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn import_chain_past_the_recursion_limit_is_rejected() {
+        let dir = temp_dir("import-depth");
+        // f0 -> f1 -> f2 -> f3 -> f4 -> f5, no repeats, so this can only fail on depth.
+        for i in 0..5 {
+            std::fs::write(dir.join(format!("f{i}.yaml")), format!("import: f{}.yaml\n", i + 1)).unwrap();
+        }
+        std::fs::write(dir.join("f5.yaml"), "key: leaf\n").unwrap();
+
+        let config = FileConfiguration::new("test".to_string(), dir.clone(), None);
+        let err = config.load_config("f0.yaml").await.unwrap_err();
+        assert!(err.contains("depth"), "unexpected error: {err}");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn migration_keeps_the_files_own_import_and_overrides_instead_of_collapsing_them() {
+        let dir = temp_dir("migration-preserves-import");
+        std::fs::write(dir.join("main.yaml"), "config-version: 1\nimport: extra.yaml\nkey: custom\n").unwrap();
+        std::fs::write(dir.join("extra.yaml"), "extra_key: extra_value\n").unwrap();
+
+        let embedded = b"config-version: 2\nkey: default\nnew_key: added\n".to_vec();
+        let config = FileConfiguration::new("test".to_string(), dir.clone(), Some(embedded));
+
+        config.load_config("main.yaml").await.unwrap();
+
+        let on_disk = std::fs::read_to_string(dir.join("main.yaml")).unwrap();
+        let migrated: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(&on_disk).unwrap();
+
+        // The import directive survives migration instead of being collapsed into this file...
+        assert_eq!(migrated["import"], serde_yaml::Value::from("extra.yaml"));
+        // ...and the imported file's own keys never get inlined here.
+        assert!(!migrated.contains_key("extra_key"));
+        // The user's override of an existing key is kept...
+        assert_eq!(migrated["key"], serde_yaml::Value::from("custom"));
+        // ...while a genuinely new default key is added.
+        assert_eq!(migrated["new_key"], serde_yaml::Value::from("added"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
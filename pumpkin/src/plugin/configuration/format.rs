@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+/// A config file's on-disk encoding, auto-detected from its filename's extension.
+///
+/// Everything is normalized into the same `HashMap<String, serde_yaml::Value>` shape
+/// `Configuration` already uses, so the rest of the config system doesn't need to know or
+/// care which format a given plugin's file is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl FileFormat {
+    /// All supported formats, used to enumerate every extension a given config stem could be
+    /// written under (see [`super::file_configuration::FileConfiguration`]'s duplicate-config
+    /// detection).
+    #[must_use]
+    pub fn all() -> [Self; 3] {
+        [Self::Yaml, Self::Toml, Self::Json]
+    }
+
+    /// Detect the format from a filename's extension. Anything unrecognized (including no
+    /// extension) falls back to YAML, matching this crate's original, YAML-only behavior.
+    #[must_use]
+    pub fn from_filename(filename: &str) -> Self {
+        match std::path::Path::new(filename)
+            .extension()
+            .and_then(|ext| ext.to_str())
+        {
+            Some("toml") => Self::Toml,
+            Some("json") => Self::Json,
+            _ => Self::Yaml,
+        }
+    }
+
+    /// Parse `content` in this format into the generic map `Configuration` stores.
+    pub fn parse(self, content: &str) -> Result<HashMap<String, serde_yaml::Value>, String> {
+        let value = match self {
+            Self::Yaml => {
+                serde_yaml::from_str::<serde_yaml::Value>(content).map_err(|e| format!("Failed to parse YAML: {e}"))?
+            }
+            Self::Toml => {
+                let value = toml::from_str::<toml::Value>(content).map_err(|e| format!("Failed to parse TOML: {e}"))?;
+                serde_yaml::to_value(value).map_err(|e| format!("Failed to normalize TOML: {e}"))?
+            }
+            Self::Json => {
+                let value =
+                    serde_json::from_str::<serde_json::Value>(content).map_err(|e| format!("Failed to parse JSON: {e}"))?;
+                serde_yaml::to_value(value).map_err(|e| format!("Failed to normalize JSON: {e}"))?
+            }
+        };
+
+        Ok(match value {
+            serde_yaml::Value::Mapping(map) => map
+                .into_iter()
+                .filter_map(|(key, value)| key.as_str().map(|k| (k.to_string(), value)))
+                .collect(),
+            _ => HashMap::new(),
+        })
+    }
+
+    /// Serialize `data` back into this format's on-disk text representation.
+    pub fn serialize(self, data: &HashMap<String, serde_yaml::Value>) -> Result<String, String> {
+        match self {
+            Self::Yaml => serde_yaml::to_string(data).map_err(|e| format!("Failed to serialize YAML: {e}")),
+            Self::Toml => toml::to_string_pretty(data).map_err(|e| format!("Failed to serialize TOML: {e}")),
+            Self::Json => serde_json::to_string_pretty(data).map_err(|e| format!("Failed to serialize JSON: {e}")),
+        }
+    }
+
+    /// The filename extensions (without a leading dot) this format is conventionally stored
+    /// under.
+    #[must_use]
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::Yaml => &["yaml", "yml"],
+            Self::Toml => &["toml"],
+            Self::Json => &["json"],
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+/// Deep-merges `overlay` into `base`: a nested mapping in both is merged key-by-key instead
+/// of the overlay's mapping replacing the base's wholesale, so e.g. overriding one field of a
+/// `database:` section in a user's file doesn't wipe out the embedded default's other fields.
+pub fn deep_merge(base: &mut HashMap<String, serde_yaml::Value>, overlay: HashMap<String, serde_yaml::Value>) {
+    for (key, overlay_value) in overlay {
+        let merged = match base.remove(&key) {
+            Some(base_value) => merge_value(base_value, overlay_value),
+            None => overlay_value,
+        };
+        base.insert(key, merged);
+    }
+}
+
+fn merge_value(base: serde_yaml::Value, overlay: serde_yaml::Value) -> serde_yaml::Value {
+    match (base, overlay) {
+        (serde_yaml::Value::Mapping(mut base_map), serde_yaml::Value::Mapping(overlay_map)) => {
+            for (key, overlay_value) in overlay_map {
+                let merged = match base_map.remove(&key) {
+                    Some(base_value) => merge_value(base_value, overlay_value),
+                    None => overlay_value,
+                };
+                base_map.insert(key, merged);
+            }
+            serde_yaml::Value::Mapping(base_map)
+        }
+        // Any other type pairing (scalar vs mapping, differing scalar types, etc.) is a plain
+        // override: the overlay always wins.
+        (_, overlay) => overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::deep_merge;
+    use std::collections::HashMap;
+
+    fn parse(yaml: &str) -> HashMap<String, serde_yaml::Value> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn overlay_scalar_overrides_base_scalar() {
+        let mut base = parse("port: 25565\nname: base");
+        let overlay = parse("port: 25566");
+
+        deep_merge(&mut base, overlay);
+
+        assert_eq!(base["port"], serde_yaml::Value::from(25566));
+        assert_eq!(base["name"], serde_yaml::Value::from("base"));
+    }
+
+    #[test]
+    fn nested_mapping_merges_key_by_key_instead_of_replacing_wholesale() {
+        let mut base = parse("database:\n  host: localhost\n  port: 5432");
+        let overlay = parse("database:\n  port: 6543");
+
+        deep_merge(&mut base, overlay);
+
+        let database = base["database"].as_mapping().unwrap();
+        assert_eq!(database["host"], serde_yaml::Value::from("localhost"));
+        assert_eq!(database["port"], serde_yaml::Value::from(6543));
+    }
+
+    #[test]
+    fn overlay_mapping_over_base_scalar_replaces_it() {
+        let mut base = parse("database: disabled");
+        let overlay = parse("database:\n  host: localhost");
+
+        deep_merge(&mut base, overlay);
+
+        assert!(base["database"].is_mapping());
+    }
+
+    #[test]
+    fn a_key_only_present_in_the_overlay_is_added() {
+        let mut base = parse("name: base");
+        let overlay = parse("extra: value");
+
+        deep_merge(&mut base, overlay);
+
+        assert_eq!(base["extra"], serde_yaml::Value::from("value"));
+    }
+}
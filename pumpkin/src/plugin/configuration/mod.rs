@@ -9,8 +9,13 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 pub mod file_configuration;
+pub mod format;
+pub(crate) mod merge;
+pub mod watch;
 
 pub use file_configuration::FileConfiguration;
+pub use format::FileFormat;
+pub use watch::ConfigWatcher;
 
 /// Macro to include a resource file at compile time
 #[macro_export]
@@ -20,11 +25,17 @@ macro_rules! include_plugin_resource {
     };
 }
 
-/// Represents a plugin configuration loaded from YAML
+/// Represents a plugin configuration loaded from YAML, TOML, or JSON
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Configuration {
     /// The raw configuration data as a HashMap
     pub data: HashMap<String, serde_yaml::Value>,
+    /// Upper-cased plugin name used to build the `PUMPKIN_<PLUGIN>_<KEY>` environment
+    /// variable that the `get*` accessors check before falling back to `data`. Empty unless
+    /// this `Configuration` came from [`FileConfiguration::load_config`]; while it is empty,
+    /// the env layer is simply never consulted.
+    #[serde(skip)]
+    env_prefix: String,
 }
 
 impl Configuration {
@@ -32,12 +43,13 @@ impl Configuration {
     pub fn new() -> Self {
         Self {
             data: HashMap::new(),
+            env_prefix: String::new(),
         }
     }
 
     /// Get a string value from the configuration
     pub fn get_string(&self, path: &str) -> Option<String> {
-        self.get_value(path).and_then(|v| v.as_str()).map(|s| s.to_string())
+        self.get_value(path).and_then(|v| v.as_str().map(str::to_string))
     }
 
     /// Get a string value with a default
@@ -77,6 +89,10 @@ impl Configuration {
     }
 
     /// Get a nested configuration section
+    ///
+    /// The returned `Configuration` does not inherit the env-var override layer, since its
+    /// paths are relative to the section rather than the root and so can't reconstruct the
+    /// full `PUMPKIN_<PLUGIN>_<KEY>` name on their own.
     pub fn get_section(&self, path: &str) -> Option<Configuration> {
         self.get_value(path).and_then(|v| {
             v.as_mapping().map(|map| {
@@ -86,39 +102,85 @@ impl Configuration {
                         data.insert(key_str.to_string(), value.clone());
                     }
                 }
-                Configuration { data }
+                Configuration {
+                    data,
+                    env_prefix: String::new(),
+                }
             })
         })
     }
 
-                /// Get a raw value from the configuration
-    fn get_value(&self, path: &str) -> Option<&serde_yaml::Value> {
-        let parts: Vec<&str> = path.split('.').collect();
+    /// Resolve `path` (dot-separated, e.g. `"database.host"`) against, in priority order, the
+    /// `PUMPKIN_<PLUGIN>_<KEY>` environment variable and then the layered file data.
+    fn get_value(&self, path: &str) -> Option<serde_yaml::Value> {
+        if !self.env_prefix.is_empty() {
+            if let Ok(raw) = std::env::var(self.env_var_name(path)) {
+                // A plain string override still has to parse as a Value so get_int/get_bool
+                // see the right type; a non-YAML-ish value (like an unquoted hostname) just
+                // becomes a YAML string, which is what we want anyway.
+                return Some(serde_yaml::from_str(&raw).unwrap_or(serde_yaml::Value::String(raw)));
+            }
+        }
 
-        // For now, just handle top-level keys
-        if parts.len() == 1 {
-            return self.data.get(parts[0]);
+        let mut parts = path.split('.');
+        let mut current = self.data.get(parts.next()?)?;
+        for part in parts {
+            current = current
+                .as_mapping()?
+                .get(&serde_yaml::Value::String(part.to_string()))?;
         }
+        Some(current.clone())
+    }
 
-        // For nested paths, we'll need a more complex implementation
-        // For now, return None for nested paths
-        None
+    fn env_var_name(&self, path: &str) -> String {
+        format!(
+            "PUMPKIN_{}_{}",
+            self.env_prefix,
+            path.to_uppercase().replace('.', "_")
+        )
     }
 
-        /// Set a value in the configuration
+    /// Set a value in the configuration, creating any intermediate nested sections needed.
     pub fn set(&mut self, path: &str, value: serde_yaml::Value) {
         let parts: Vec<&str> = path.split('.').collect();
-        if parts.is_empty() {
+        let Some((first, rest)) = parts.split_first() else {
+            return;
+        };
+
+        if rest.is_empty() {
+            self.data.insert((*first).to_string(), value);
             return;
         }
 
-        let current_map = &mut self.data;
+        let root = self
+            .data
+            .entry((*first).to_string())
+            .or_insert_with(|| serde_yaml::Value::Mapping(serde_yaml::Mapping::new()));
+        set_nested(root, rest, value);
+    }
+}
+
+/// Walks/creates mappings for every path segment but the last, then inserts `value` under it.
+fn set_nested(current: &mut serde_yaml::Value, parts: &[&str], value: serde_yaml::Value) {
+    let Some((first, rest)) = parts.split_first() else {
+        return;
+    };
 
-        // For now, just set at the top level
-        if let Some(last_part) = parts.last() {
-            current_map.insert(last_part.to_string(), value);
-        }
+    if !current.is_mapping() {
+        *current = serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+    }
+    let mapping = current.as_mapping_mut().expect("just ensured this is a mapping");
+    let key = serde_yaml::Value::String((*first).to_string());
+
+    if rest.is_empty() {
+        mapping.insert(key, value);
+        return;
+    }
+
+    if !matches!(mapping.get(&key), Some(serde_yaml::Value::Mapping(_))) {
+        mapping.insert(key.clone(), serde_yaml::Value::Mapping(serde_yaml::Mapping::new()));
     }
+    set_nested(mapping.get_mut(&key).expect("just inserted"), rest, value);
 }
 
 impl Default for Configuration {
@@ -182,4 +244,29 @@ pub trait ConfigurablePlugin {
     async fn get_config(&self) -> Result<Configuration, String> {
         self.load_config("config.yml").await
     }
+
+    /// Persist a mutated configuration back to disk, in the format implied by `filename`'s
+    /// extension
+    async fn save_config(&self, filename: &str, config: &Configuration) -> Result<(), String> {
+        let config_manager = FileConfiguration::new(
+            self.get_plugin_name().to_string(),
+            self.get_data_folder(),
+            self.get_embedded_resource(filename),
+        );
+
+        config_manager.save_config(filename, config).await
+    }
+
+    /// Watch `filename` on disk and get live-reloaded configs back through a [`ConfigWatcher`],
+    /// so admins can edit the file and have it take effect without restarting the server.
+    async fn watch_config(&self, filename: &str) -> Result<watch::ConfigWatcher, String> {
+        let initial = self.load_config(filename).await?;
+        let config_manager = FileConfiguration::new(
+            self.get_plugin_name().to_string(),
+            self.get_data_folder(),
+            self.get_embedded_resource(filename),
+        );
+
+        config_manager.watch_config(filename, initial)
+    }
 }
@@ -0,0 +1,109 @@
+use crate::plugin::configuration::Configuration;
+use crate::plugin::configuration::file_configuration::FileConfiguration;
+use notify::{Event as NotifyEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{RwLock, broadcast};
+use tokio::time::Instant;
+
+/// Filesystem events are coalesced if they land within this window of the last one we acted
+/// on, so an editor's write-then-rename (or a `git checkout`) triggers one reload, not several.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches a single config file on disk and reloads it through [`FileConfiguration::load_config`]
+/// whenever it changes, broadcasting the updated [`Configuration`] to subscribers.
+///
+/// Reloading through the real `load_config` pipeline (not a bare file parse) means a config
+/// that relies on the embedded default, `import:`-ed files, or env var overrides keeps behaving
+/// the same way live as it does on a fresh plugin load.
+///
+/// A reload that fails to parse is logged and discarded: [`Self::current`] keeps returning the
+/// last successfully parsed configuration rather than leaving plugins without settings because
+/// of a momentary syntax error in a hand-edited file.
+pub struct ConfigWatcher {
+    current: Arc<RwLock<Configuration>>,
+    sender: broadcast::Sender<Configuration>,
+    _watcher: RecommendedWatcher,
+}
+
+impl ConfigWatcher {
+    /// Start watching `path` (`config`'s `filename`, already joined onto its data folder),
+    /// whose on-disk contents are assumed to already match `initial`.
+    pub fn spawn(
+        path: PathBuf,
+        initial: Configuration,
+        plugin_name: String,
+        config: FileConfiguration,
+        filename: String,
+    ) -> Result<Self, String> {
+        let current = Arc::new(RwLock::new(initial));
+        let (sender, _) = broadcast::channel(8);
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let watched_name = path
+            .file_name()
+            .ok_or_else(|| format!("Invalid config path: {}", path.display()))?
+            .to_owned();
+
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<NotifyEvent>| {
+            if let Ok(event) = event {
+                if event.paths.iter().any(|p| p.file_name() == Some(watched_name.as_ref())) {
+                    let _ = event_tx.send(event);
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to start config watcher: {e}"))?;
+
+        // Watching the file itself instead of its directory breaks on Linux's inotify backend
+        // once the file's inode is replaced, which is exactly what `save_config`'s
+        // write-then-rename and the migration write-back do on every save — so the directory is
+        // watched instead and events are filtered down to this file's name above.
+        let watch_dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        watcher
+            .watch(watch_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {}: {e}", watch_dir.display()))?;
+
+        let current_for_task = current.clone();
+        let sender_for_task = sender.clone();
+        tokio::spawn(async move {
+            let mut last_reload = Instant::now() - DEBOUNCE;
+            while event_rx.recv().await.is_some() {
+                if last_reload.elapsed() < DEBOUNCE {
+                    continue;
+                }
+                tokio::time::sleep(DEBOUNCE).await;
+                last_reload = Instant::now();
+
+                match config.load_config(&filename).await {
+                    Ok(reloaded) => {
+                        *current_for_task.write().await = reloaded.clone();
+                        let _ = sender_for_task.send(reloaded);
+                    }
+                    Err(e) => {
+                        log::warn!(
+                            "[{plugin_name}] Failed to reload config {}: {e} (keeping previous config in effect)",
+                            path.display()
+                        );
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            current,
+            sender,
+            _watcher: watcher,
+        })
+    }
+
+    /// The most recently successfully parsed configuration.
+    pub async fn current(&self) -> Configuration {
+        self.current.read().await.clone()
+    }
+
+    /// Subscribe to be notified of every successful reload. Failed reloads are never sent.
+    pub fn subscribe(&self) -> broadcast::Receiver<Configuration> {
+        self.sender.subscribe()
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Topologically orders plugins by their dependencies using Kahn's algorithm.
+///
+/// Each entry is `(name, depends, soft_depends)`. A soft dependency only affects ordering
+/// when the referenced plugin is also present in `nodes`; an absent soft dependency is
+/// silently ignored rather than treated as an edge. On success, returns the plugin names in
+/// the order they should be loaded (dependencies before dependents). If a cycle prevents a
+/// full ordering, returns the names of every plugin that could not be ordered: those in a
+/// cycle and those that depend, directly or not, on one.
+pub fn topological_order(nodes: &[(String, Vec<String>, Vec<String>)]) -> Result<Vec<String>, Vec<String>> {
+    let names: HashSet<&str> = nodes.iter().map(|(name, ..)| name.as_str()).collect();
+
+    let mut in_degree: HashMap<&str, usize> =
+        nodes.iter().map(|(name, ..)| (name.as_str(), 0)).collect();
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+
+    for (name, depends, soft_depends) in nodes {
+        for dep in depends.iter().chain(soft_depends) {
+            if names.contains(dep.as_str()) {
+                dependents.entry(dep.as_str()).or_default().push(name.as_str());
+                *in_degree.get_mut(name.as_str()).unwrap() += 1;
+            }
+        }
+    }
+
+    let mut queue: VecDeque<&str> = in_degree
+        .iter()
+        .filter(|(_, &degree)| degree == 0)
+        .map(|(&name, _)| name)
+        .collect();
+    // Iteration order over a HashMap is not stable; sort the initial frontier so load order
+    // only depends on the dependency graph, not on hashing.
+    let mut initial: Vec<&str> = queue.drain(..).collect();
+    initial.sort_unstable();
+    queue.extend(initial);
+
+    let mut order = Vec::with_capacity(nodes.len());
+    while let Some(name) = queue.pop_front() {
+        order.push(name.to_string());
+        if let Some(next) = dependents.get(name) {
+            for &dependent in next {
+                let degree = in_degree.get_mut(dependent).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    queue.push_back(dependent);
+                }
+            }
+        }
+    }
+
+    if order.len() == nodes.len() {
+        Ok(order)
+    } else {
+        let resolved: HashSet<&str> = order.iter().map(String::as_str).collect();
+        Err(nodes
+            .iter()
+            .filter(|(name, ..)| !resolved.contains(name.as_str()))
+            .map(|(name, ..)| name.clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::topological_order;
+
+    fn node(name: &str, depends: &[&str], soft_depends: &[&str]) -> (String, Vec<String>, Vec<String>) {
+        (
+            name.to_string(),
+            depends.iter().map(ToString::to_string).collect(),
+            soft_depends.iter().map(ToString::to_string).collect(),
+        )
+    }
+
+    #[test]
+    fn orders_dependencies_before_dependents() {
+        let nodes = vec![
+            node("b", &["a"], &[]),
+            node("a", &[], &[]),
+            node("c", &["a", "b"], &[]),
+        ];
+
+        let order = topological_order(&nodes).unwrap();
+        assert_eq!(order, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn absent_soft_dependency_is_ignored_rather_than_an_edge() {
+        let nodes = vec![node("a", &[], &["missing"])];
+        assert_eq!(topological_order(&nodes).unwrap(), vec!["a"]);
+    }
+
+    #[test]
+    fn direct_cycle_reports_only_the_stuck_plugins() {
+        let nodes = vec![
+            node("a", &["b"], &[]),
+            node("b", &["a"], &[]),
+            node("c", &[], &[]),
+        ];
+
+        let mut stuck = topological_order(&nodes).unwrap_err();
+        stuck.sort();
+        assert_eq!(stuck, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn resolvable_nodes_outside_the_cycle_still_order_cleanly() {
+        let nodes = vec![
+            node("a", &["b"], &[]),
+            node("b", &["a"], &[]),
+            node("c", &[], &[]),
+        ];
+
+        let cycle = topological_order(&nodes).unwrap_err();
+        let resolvable: Vec<_> = nodes
+            .into_iter()
+            .filter(|(name, ..)| !cycle.contains(name))
+            .collect();
+
+        assert_eq!(topological_order(&resolvable).unwrap(), vec!["c"]);
+    }
+}
@@ -0,0 +1,58 @@
+use std::{any::Any, path::Path};
+
+use async_trait::async_trait;
+use thiserror::Error;
+
+use super::{Plugin, PluginMetadata};
+
+pub mod native;
+
+/// Errors a `PluginLoader` can surface while probing, loading, or unloading a plugin artifact.
+#[derive(Error, Debug)]
+pub enum LoaderError {
+    #[error("Failed to open plugin library: {0}")]
+    OpenFailed(String),
+
+    #[error("Plugin is missing required symbol: {0}")]
+    MissingSymbol(String),
+
+    #[error("Plugin failed to initialize: {0}")]
+    InitializationFailed(String),
+
+    #[error("Plugin cannot be unloaded on this platform")]
+    UnloadUnsupported,
+}
+
+/// A backend capable of turning a file on disk into a running `Plugin` instance.
+///
+/// `NativePluginLoader` handles compiled `.so`/`.dll`/`.dylib` artifacts; other loaders
+/// (e.g. a scripting runtime) can be registered via `PluginManager::add_loader`.
+#[async_trait]
+pub trait PluginLoader: Send + Sync {
+    /// Whether this loader recognizes the given file.
+    fn can_load(&self, path: &Path) -> bool;
+
+    /// Whether plugins loaded by this loader can later be unloaded without a restart.
+    fn can_unload(&self) -> bool;
+
+    /// Read just the plugin's declared metadata from `path`, without constructing a running
+    /// instance.
+    ///
+    /// This is what makes the metadata cache (`PluginCache`) worth anything: building the
+    /// dependency graph for every file in the plugin directory only needs `name`/`depends`/
+    /// `soft_depends`, so a candidate that ends up skipped (a missing hard dependency, a
+    /// dependency cycle, a config filter) never pays for a full [`Self::load`] at all. A
+    /// candidate that *is* going to run still calls [`Self::load`] exactly once, right before
+    /// `on_load`.
+    async fn probe_metadata(&self, path: &Path) -> Result<PluginMetadata<'static>, LoaderError>;
+
+    /// Load the plugin at `path`, returning its instance, metadata, and any loader-private
+    /// handle needed to unload it later.
+    async fn load(
+        &self,
+        path: &Path,
+    ) -> Result<(Box<dyn Plugin>, PluginMetadata<'static>, Box<dyn Any + Send + Sync>), LoaderError>;
+
+    /// Tear down a previously loaded plugin using the handle returned from `load`.
+    async fn unload(&self, data: Box<dyn Any + Send + Sync>) -> Result<(), LoaderError>;
+}
@@ -0,0 +1,47 @@
+use std::{any::Any, path::Path};
+
+use async_trait::async_trait;
+
+use super::{LoaderError, Plugin, PluginLoader};
+use crate::plugin::PluginMetadata;
+
+/// Loads plugins compiled as native dynamic libraries (`.so`/`.dll`/`.dylib`) exposing a
+/// `pumpkin_plugin!`-generated entry point.
+pub struct NativePluginLoader;
+
+#[async_trait]
+impl PluginLoader for NativePluginLoader {
+    fn can_load(&self, path: &Path) -> bool {
+        matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("so" | "dll" | "dylib")
+        )
+    }
+
+    fn can_unload(&self) -> bool {
+        // Unix can safely dlclose a library; Windows keeps it mapped for the process lifetime.
+        cfg!(not(target_os = "windows"))
+    }
+
+    async fn probe_metadata(&self, path: &Path) -> Result<PluginMetadata<'static>, LoaderError> {
+        let _ = path;
+        Err(LoaderError::OpenFailed(
+            "native plugin loading is not available in this build".to_string(),
+        ))
+    }
+
+    async fn load(
+        &self,
+        path: &Path,
+    ) -> Result<(Box<dyn Plugin>, PluginMetadata<'static>, Box<dyn Any + Send + Sync>), LoaderError>
+    {
+        let _ = path;
+        Err(LoaderError::OpenFailed(
+            "native plugin loading is not available in this build".to_string(),
+        ))
+    }
+
+    async fn unload(&self, _data: Box<dyn Any + Send + Sync>) -> Result<(), LoaderError> {
+        Ok(())
+    }
+}
@@ -11,9 +11,14 @@ use thiserror::Error;
 use tokio::sync::RwLock;
 
 pub mod api;
+mod cache;
 pub mod config;
+mod dependency;
 pub mod loader;
 
+use cache::PluginCache;
+use config::PluginManagerConfig;
+
 use crate::{PERMISSION_MANAGER, server::Server};
 pub use api::*;
 
@@ -138,6 +143,9 @@ pub struct PluginManager {
     unloaded_files: HashSet<PathBuf>,
     // Self-reference for sharing with contexts
     self_ref: Option<Arc<RwLock<PluginManager>>>,
+    /// Cached metadata from the last `load_plugins` scan, keyed by file path.
+    cache: PluginCache,
+    config: PluginManagerConfig,
 }
 
 /// Represents a successfully loaded plugin
@@ -150,6 +158,37 @@ struct LoadedPlugin {
     loader: Arc<dyn PluginLoader>,
     loader_data: Box<dyn Any + Send + Sync>,
     is_active: bool,
+    /// The file this plugin was loaded from, kept around so it can be reloaded in place.
+    path: PathBuf,
+}
+
+/// A plugin file that has been probed (metadata extracted via
+/// [`PluginLoader::probe_metadata`]) but not yet loaded.
+///
+/// Splitting discovery from initialization lets `load_plugins` build a dependency graph over
+/// every candidate, and check for a missing hard dependency, before the (much more expensive)
+/// [`PluginLoader::load`] is ever called - so a candidate that gets skipped for either reason
+/// never pays for a full load. Whether `metadata` came from [`PluginCache`] or a fresh probe
+/// makes no difference here: either way, loading is deferred to [`PluginManager::initialize_candidate`].
+struct PluginCandidate {
+    path: PathBuf,
+    metadata: PluginMetadata<'static>,
+}
+
+impl PluginCandidate {
+    fn metadata(&self) -> &PluginMetadata<'static> {
+        &self.metadata
+    }
+}
+
+/// A snapshot of a loaded plugin's runtime state, returned by [`PluginManager::list_plugins`]
+/// for admin tooling (e.g. a `/plugin list` command).
+#[derive(Debug, Clone)]
+pub struct PluginInfo {
+    pub name: String,
+    pub version: String,
+    pub is_active: bool,
+    pub path: PathBuf,
 }
 
 /// Error types for plugin management
@@ -169,6 +208,15 @@ pub enum ManagerError {
 
     #[error("Plugin manager not initialized properly")]
     ManagerNotInitialized,
+
+    #[error("Plugin {0} depends on {1}, which is not loaded")]
+    DependencyMissing(String, String),
+
+    #[error("Plugin dependency cycle detected: {}", .0.join(" -> "))]
+    DependencyCycle(Vec<String>),
+
+    #[error("Plugin {0} is still in use by: {}", .1.join(", "))]
+    InUseBy(String, Vec<String>),
 }
 
 impl Default for PluginManager {
@@ -180,6 +228,8 @@ impl Default for PluginManager {
             handlers: Arc::new(RwLock::new(HashMap::new())),
             unloaded_files: HashSet::new(),
             self_ref: None,
+            cache: PluginCache::default(),
+            config: PluginManagerConfig::default(),
         }
     }
 }
@@ -201,7 +251,9 @@ impl PluginManager {
             .collect();
 
         for name in plugin_names {
-            if let Err(e) = self.unload_plugin(name).await {
+            // The server is shutting down every plugin anyway, so dependents never need to
+            // block this; force the cascade instead of erroring on in-use plugins.
+            if let Err(e) = self.unload_plugin(name, true).await {
                 log::error!("Failed to unload plugin {name}: {e}");
             }
         }
@@ -209,6 +261,37 @@ impl PluginManager {
         Ok(())
     }
 
+    /// Names of currently loaded plugins that declare `name` as a hard or soft dependency.
+    fn dependents_of(&self, name: &str) -> Vec<String> {
+        self.plugins
+            .iter()
+            .filter(|p| {
+                p.metadata.name != name
+                    && (p.metadata.depends.contains(&name) || p.metadata.soft_depends.contains(&name))
+            })
+            .map(|p| p.metadata.name.to_string())
+            .collect()
+    }
+
+    /// All plugins that transitively depend on `name`, discovered by walking `dependents_of`
+    /// depth-first from the root.
+    fn transitive_dependents(&self, name: &str) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut stack = vec![name.to_string()];
+        let mut result = Vec::new();
+
+        while let Some(current) = stack.pop() {
+            for dependent in self.dependents_of(&current) {
+                if seen.insert(dependent.clone()) {
+                    stack.push(dependent.clone());
+                    result.push(dependent);
+                }
+            }
+        }
+
+        result
+    }
+
     /// Add a new plugin loader implementation
     pub async fn add_loader(&mut self, loader: Arc<dyn PluginLoader>) {
         self.loaders.push(loader);
@@ -237,6 +320,11 @@ impl PluginManager {
         self.self_ref = Some(self_ref);
     }
 
+    /// Set the plugin directory and blacklist/whitelist read from the server config
+    pub fn set_config(&mut self, config: PluginManagerConfig) {
+        self.config = config;
+    }
+
     /// Get a clone of the loaders for context use
     #[must_use]
     pub fn get_loaders(&self) -> Vec<Arc<dyn PluginLoader>> {
@@ -244,25 +332,112 @@ impl PluginManager {
     }
 
     /// Load all plugins from the plugin directory
+    ///
+    /// Every file in the directory that the plugin-manager config doesn't filter out is
+    /// probed for metadata first, then the whole batch is
+    /// loaded in dependency order (via [`dependency::topological_order`]) so a plugin that
+    /// depends on another always has it available during `on_load`, regardless of the
+    /// order `read_dir` happened to yield.
+    ///
+    /// Metadata is served from `<plugin_dir>/.plugin-cache.msgpackz` for any file whose mtime
+    /// and size haven't changed since the last boot, so only new or modified plugins are
+    /// actually dynamically opened during this scan.
     pub async fn load_plugins(&mut self) -> Result<(), ManagerError> {
-        const PLUGIN_DIR: &str = "./plugins";
-        let path = Path::new(PLUGIN_DIR);
+        let path = PathBuf::from(&self.config.plugin_dir);
 
         if !path.exists() {
-            std::fs::create_dir(path)?;
+            std::fs::create_dir(&path)?;
             return Ok(());
         }
 
-        for entry in std::fs::read_dir(path)? {
+        let cache_path = path.join(".plugin-cache.msgpackz");
+        self.cache = PluginCache::load(&cache_path);
+
+        let mut candidates = Vec::new();
+        for entry in std::fs::read_dir(&path)? {
             let entry = entry?;
-            let path = entry.path();
+            let file_path = entry.path();
+
+            if file_path.is_dir() {
+                continue;
+            }
 
-            if path.is_dir() {
+            let stem = file_path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if self.config.is_filtered(&stem) {
+                log::info!("Skipping plugin {stem} (filtered by plugin-manager config)");
                 continue;
             }
 
-            if let Err(err) = self.try_load_plugin(&path).await {
+            if let Some(candidate) = self.probe_plugin_cached(&file_path, &cache_path).await {
+                candidates.push(candidate);
+            }
+        }
+
+        let nodes: Vec<_> = candidates
+            .iter()
+            .map(|candidate| {
+                (
+                    candidate.metadata().name.to_string(),
+                    candidate.metadata().depends.iter().map(ToString::to_string).collect(),
+                    candidate.metadata().soft_depends.iter().map(ToString::to_string).collect(),
+                )
+            })
+            .collect();
+
+        let order = match dependency::topological_order(&nodes) {
+            Ok(order) => order,
+            Err(cycle) => {
+                log::error!(
+                    "Plugin dependency cycle detected, skipping cyclic plugins: {}",
+                    cycle.join(" -> ")
+                );
+                let cyclic: HashSet<&str> = cycle.iter().map(String::as_str).collect();
+                let resolvable: Vec<_> = nodes
+                    .iter()
+                    .filter(|(name, ..)| !cyclic.contains(name.as_str()))
+                    .cloned()
+                    .collect();
+                // Every node outside the cyclic set was already fully resolved by the first
+                // pass, so this can't fail again.
+                dependency::topological_order(&resolvable)
+                    .expect("removing the cyclic subset leaves an acyclic graph")
+            }
+        };
+
+        for name in order {
+            let index = candidates
+                .iter()
+                .position(|candidate| candidate.metadata().name == name)
+                .expect("name came from this batch's own dependency graph");
+            let candidate = candidates.remove(index);
+
+            if let Some(missing) = candidate
+                .metadata()
+                .depends
+                .iter()
+                .find(|dep| !self.is_plugin_loaded(dep))
+            {
+                let err = ManagerError::DependencyMissing(
+                    candidate.metadata().name.to_string(),
+                    (*missing).to_string(),
+                );
                 log::error!("{err}");
+                continue;
+            }
+
+            match self.initialize_candidate(candidate).await {
+                Ok(plugin) => {
+                    log::info!(
+                        "Loaded {} ({})",
+                        plugin.metadata.name,
+                        plugin.metadata.version
+                    );
+                    self.plugins.push(plugin);
+                }
+                Err(e) => log::error!("Failed to load plugin: {e}"),
             }
         }
 
@@ -271,47 +446,113 @@ impl PluginManager {
 
     /// Attempt to load a single plugin file
     pub async fn try_load_plugin(&mut self, path: &Path) -> Result<(), ManagerError> {
+        let Some(candidate) = self.probe_plugin(path).await else {
+            return Err(ManagerError::PluginNotFound(
+                path.to_string_lossy().to_string(),
+            ));
+        };
+
+        if let Some(missing) = candidate
+            .metadata()
+            .depends
+            .iter()
+            .find(|dep| !self.is_plugin_loaded(dep))
+        {
+            let err = ManagerError::DependencyMissing(
+                candidate.metadata().name.to_string(),
+                (*missing).to_string(),
+            );
+            log::error!("{err}");
+            return Ok(());
+        }
+
+        match self.initialize_candidate(candidate).await {
+            Ok(plugin) => {
+                log::info!(
+                    "Loaded {} ({})",
+                    plugin.metadata.name,
+                    plugin.metadata.version
+                );
+                self.unloaded_files.remove(path);
+                self.plugins.push(plugin);
+                Ok(())
+            }
+            Err(e) => {
+                log::error!("Failed to load plugin {}: {}", path.display(), e);
+                Ok(())
+            }
+        }
+    }
+
+    /// Find a loader that can handle `path` and extract the plugin's metadata without
+    /// initializing it yet. Tracks the file in `unloaded_files` if no loader claims it.
+    async fn probe_plugin(&mut self, path: &Path) -> Option<PluginCandidate> {
         for loader in &self.loaders {
             if loader.can_load(path) {
-                match self.load_with_loader(loader, path).await {
-                    Ok(plugin) => {
-                        log::info!(
-                            "Loaded {} ({})",
-                            plugin.metadata.name,
-                            plugin.metadata.version
-                        );
-                        self.plugins.push(plugin);
-                        // Remove from unloaded files if it was there
-                        self.unloaded_files.remove(path);
-                        return Ok(());
-                    }
+                return match loader.probe_metadata(path).await {
+                    Ok(metadata) => Some(PluginCandidate {
+                        path: path.to_path_buf(),
+                        metadata,
+                    }),
                     Err(e) => {
-                        log::error!("Failed to load plugin {}: {}", path.display(), e);
-                        return Ok(());
+                        log::error!("Failed to probe plugin {}: {}", path.display(), e);
+                        None
                     }
-                }
+                };
             }
         }
 
-        // No loader could handle this file, track it for future attempts
         self.unloaded_files.insert(path.to_path_buf());
+        None
+    }
+
+    /// Like [`Self::probe_plugin`], but first checks the metadata cache: a file whose mtime
+    /// and size match its last recorded entry is returned without ever calling a loader, and
+    /// a genuinely new or changed file has its freshly-read metadata written back to the
+    /// cache immediately after probing.
+    async fn probe_plugin_cached(&mut self, path: &Path, cache_path: &Path) -> Option<PluginCandidate> {
+        let Ok((mtime, size)) = PluginCache::stat(path) else {
+            return self.probe_plugin(path).await;
+        };
+
+        if let Some(metadata) = self.cache.lookup(path, mtime, size) {
+            return Some(PluginCandidate {
+                path: path.to_path_buf(),
+                metadata,
+            });
+        }
 
-        Err(ManagerError::PluginNotFound(
-            path.to_string_lossy().to_string(),
-        ))
+        let candidate = self.probe_plugin(path).await?;
+        self.cache
+            .upsert(cache_path, path.to_path_buf(), mtime, size, candidate.metadata());
+        Some(candidate)
     }
 
-    /// Load plugin using a specific loader
-    async fn load_with_loader(
+    /// Actually load a probed candidate and run its `on_load` hook, turning it into a
+    /// [`LoadedPlugin`].
+    ///
+    /// This is the only place [`PluginLoader::load`] is called for a batch-scanned candidate -
+    /// deliberately deferred this far so a candidate that [`Self::load_plugins`] skips (missing
+    /// hard dependency, stuck in a cycle) never pays for it, regardless of whether its metadata
+    /// came from [`PluginCache`] or a fresh probe.
+    async fn initialize_candidate(
         &self,
-        loader: &Arc<dyn PluginLoader>,
-        path: &Path,
+        candidate: PluginCandidate,
     ) -> Result<LoadedPlugin, ManagerError> {
+        let PluginCandidate { path, metadata } = candidate;
+
+        let loader = self
+            .loaders
+            .iter()
+            .find(|loader| loader.can_load(&path))
+            .ok_or_else(|| ManagerError::PluginNotFound(path.to_string_lossy().to_string()))?
+            .clone();
+        let (mut instance, _fresh_metadata, loader_data) = loader.load(&path).await?;
+
         let server = self
             .server
             .as_ref()
             .ok_or(ManagerError::ServerNotInitialized)?;
-        let (mut instance, metadata, loader_data) = loader.load(path).await?;
 
         // Get a self_ref for the context or fail if not set
         let self_ref = self
@@ -329,10 +570,10 @@ impl PluginManager {
 
         if let Err(e) = instance.on_load(&context).await {
             let data = loader_data;
-            let loader = loader.clone();
+            let loader_for_cleanup = loader.clone();
             let _ = instance.on_unload(&context).await;
             tokio::spawn(async move {
-                loader.unload(data).await.ok();
+                loader_for_cleanup.unload(data).await.ok();
             });
             return Err(ManagerError::LoaderError(
                 LoaderError::InitializationFailed(e),
@@ -342,12 +583,54 @@ impl PluginManager {
         Ok(LoadedPlugin {
             metadata,
             instance,
-            loader: loader.clone(),
+            loader,
             loader_data,
             is_active: true,
+            path,
         })
     }
 
+    /// Reload a plugin in place: unload it, then load the replacement artifact from the same
+    /// path it originally came from.
+    ///
+    /// The old instance is always fully unloaded before the new one is probed, so a failed
+    /// reload leaves the plugin unloaded rather than running two instances side by side.
+    ///
+    /// Fails with `ManagerError::InUseBy` if anything still depends on `name`, same as
+    /// [`Self::unload_plugin`] with `force: false` — reloading can't force-cascade the unload,
+    /// since that would tear down the dependents without ever reloading them back.
+    pub async fn reload_plugin(&mut self, name: &str) -> Result<(), ManagerError> {
+        let path = self
+            .plugins
+            .iter()
+            .find(|p| p.metadata.name == name)
+            .map(|p| p.path.clone())
+            .ok_or_else(|| ManagerError::PluginNotFound(name.to_string()))?;
+
+        self.unload_plugin(name, false).await?;
+        self.load_plugin_from_path(&path).await
+    }
+
+    /// Load a single plugin from an arbitrary path, for runtime administration (e.g. a
+    /// `/plugin load <file>` command) rather than the startup directory scan.
+    pub async fn load_plugin_from_path(&mut self, path: &Path) -> Result<(), ManagerError> {
+        self.try_load_plugin(path).await
+    }
+
+    /// Snapshot of every loaded plugin's name, version, active state, and source path.
+    #[must_use]
+    pub fn list_plugins(&self) -> Vec<PluginInfo> {
+        self.plugins
+            .iter()
+            .map(|p| PluginInfo {
+                name: p.metadata.name.to_string(),
+                version: p.metadata.version.to_string(),
+                is_active: p.is_active,
+                path: p.path.clone(),
+            })
+            .collect()
+    }
+
     /// Checks if plugin active
     #[must_use]
     pub fn is_plugin_active(&self, name: &str) -> bool {
@@ -379,7 +662,46 @@ impl PluginManager {
     }
 
     /// Unload a plugin by name
-    pub async fn unload_plugin(&mut self, name: &str) -> Result<(), ManagerError> {
+    ///
+    /// If other loaded plugins still depend on `name`, this fails with
+    /// `ManagerError::InUseBy` unless `force` is set, in which case those dependents (and
+    /// anything depending on *them*) are unloaded first, in reverse dependency order.
+    pub async fn unload_plugin(&mut self, name: &str, force: bool) -> Result<(), ManagerError> {
+        let dependents = self.dependents_of(name);
+        if !dependents.is_empty() {
+            if !force {
+                return Err(ManagerError::InUseBy(name.to_string(), dependents));
+            }
+
+            let transitive = self.transitive_dependents(name);
+            let nodes: Vec<_> = transitive
+                .iter()
+                .map(|dependent| {
+                    let plugin = self
+                        .plugins
+                        .iter()
+                        .find(|p| p.metadata.name == dependent)
+                        .expect("transitive_dependents only returns currently loaded plugins");
+                    (
+                        dependent.clone(),
+                        plugin.metadata.depends.iter().map(ToString::to_string).collect(),
+                        plugin.metadata.soft_depends.iter().map(ToString::to_string).collect(),
+                    )
+                })
+                .collect();
+
+            // Dependencies-before-dependents load order, reversed, unloads dependents first.
+            let load_order = dependency::topological_order(&nodes).map_err(ManagerError::DependencyCycle)?;
+            for dependent in load_order.into_iter().rev() {
+                self.unload_one(&dependent).await?;
+            }
+        }
+
+        self.unload_one(name).await
+    }
+
+    /// Tear down a single loaded plugin, without considering whether anything depends on it.
+    async fn unload_one(&mut self, name: &str) -> Result<(), ManagerError> {
         let index = self
             .plugins
             .iter()
@@ -443,11 +765,21 @@ impl PluginManager {
         if let Some(server) = &self.server {
             let handlers = self.handlers.read().await;
             if let Some(handlers) = handlers.get(&E::get_name_static()) {
-                let (blocking, non_blocking): (Vec<_>, Vec<_>) =
+                let (mut blocking, non_blocking): (Vec<_>, Vec<_>) =
                     handlers.iter().partition(|h| h.is_blocking());
 
-                // Process blocking handlers first
+                // Priority determines both run order and who still gets to see a cancelled
+                // event: everything but MONITOR stops once the event is cancelled, while
+                // MONITOR handlers always run, purely to observe the final outcome.
+                blocking.sort_by_key(|h| h.get_priority());
+
                 for handler in blocking {
+                    let already_cancelled = event
+                        .as_cancellable()
+                        .is_some_and(Cancellable::is_cancelled);
+                    if already_cancelled && handler.get_priority() != EventPriority::Monitor {
+                        continue;
+                    }
                     handler.handle_blocking_dyn(server, &mut event).await;
                 }
 